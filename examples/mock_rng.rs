@@ -0,0 +1,20 @@
+use rand::rngs::mock::StepRng;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Ok,
+    Dropped,
+}
+
+fn main() {
+    // `StepRng::new(0, 1 << 62)` advances by exactly a quarter turn of the u64 space on every
+    // call, so it wraps back to 0 every 4th draw — a short, exact, fully known repeating cycle.
+    // `bernoulli_from_rng` draws a single `f64` per step (no range-rejection sampling), so the
+    // resulting stream of statuses is exactly reproducible and guaranteed to terminate.
+    let rng = StepRng::new(0, 1u64 << 62);
+    let rs = randstat::RandStat::bernoulli_from_rng(rng, 0.5, Status::Dropped, Status::Ok).unwrap();
+    for status in rs.take(8) {
+        println!("{:?}", status);
+    }
+    // Always prints: Dropped, Dropped, Ok, Ok, Dropped, Dropped, Ok, Ok
+}
@@ -4,7 +4,8 @@
 //! The original use case is in a simulation of an unreliable network. Percentages of lost messages, garbled messages,
 //! dropped connections, etc.
 
-use rand::Rng;
+use rand::rngs::{StdRng, ThreadRng};
+use rand::{Rng, SeedableRng};
 use std::error::Error;
 use std::fmt;
 
@@ -20,6 +21,33 @@ impl fmt::Display for RandStatOverflowError {
 
 impl Error for RandStatOverflowError {}
 
+/// The error type for an invalid weight vector passed to [`RandStat::weighted`]
+#[derive(Debug)]
+pub struct RandStatWeightError {}
+
+impl fmt::Display for RandStatWeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RandStat weights must be non-negative and sum to a positive total"
+        )
+    }
+}
+
+impl Error for RandStatWeightError {}
+
+/// The error type for a Bernoulli probability outside the `[0.0, 1.0]` range
+#[derive(Debug)]
+pub struct RandStatProbabilityError {}
+
+impl fmt::Display for RandStatProbabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RandStat probability must be between 0.0 and 1.0")
+    }
+}
+
+impl Error for RandStatProbabilityError {}
+
 /// The container for random status defintions
 ///
 /// There are 100 cells, each representing a probability of .01 as an integer percentage.
@@ -61,47 +89,274 @@ impl Error for RandStatOverflowError {}
 ///    assert!(c == Coin::Heads || c == Coin::Tails);
 /// }
 /// ```
-pub struct RandStat {
-    cells: [u8; 100],
+///
+/// By default `RandStat` draws from the thread-local generator, which makes each stream
+/// different from run to run. Use [`RandStat::seed_from_u64`] or [`RandStat::from_rng`] to
+/// get a reproducible stream, e.g. for replaying a simulated network trace in a test.
+///
+/// [`RandStat::new`] caps you at integer percentages and at most 100 distinct buckets; it
+/// rejects percentages that sum to more than 100 and fills any remainder with `T::default()`.
+/// For sub-percent weights, arbitrary outcome
+/// counts, or weights that don't need to sum to anything in particular, build one with
+/// [`RandStat::weighted`] instead; it samples in O(1) via Vose's alias method.
+///
+/// `RandStat` is generic over the emitted value type `T`, defaulting to `u8` so existing
+/// callers are unaffected. Simulations that want to emit a status enum, an error label, or a
+/// struct payload directly can set `T` to that type instead of round-tripping through `u8`:
+///
+/// ```
+/// #[derive(Debug, Clone, Default, PartialEq)]
+/// enum Outcome {
+///     #[default]
+///     Ok,
+///     Timeout,
+/// }
+///
+/// let init = vec![randstat::StatInit { percentage: 50, value: Outcome::Timeout }];
+/// let rs: randstat::RandStat<Outcome> = randstat::RandStat::new(&init).unwrap();
+/// for o in rs.take(100) {
+///     assert!(o == Outcome::Ok || o == Outcome::Timeout);
+/// }
+/// ```
+pub struct RandStat<T: Clone = u8, R: Rng = ThreadRng> {
+    table: Table<T>,
+    rng: R,
 }
 
 /// A single random status definition. Used for initialization.
-pub struct StatInit {
+pub struct StatInit<T> {
     pub percentage: usize,
-    pub value: u8,
+    pub value: T,
 }
 
-impl RandStat {
-    pub fn new(init_vec: &[StatInit]) -> Result<Self, RandStatOverflowError> {
-        let mut cells = [0; 100];
-        let mut index: usize = 0;
-        for init in init_vec {
-            for _ in 0..init.percentage {
-                if index >= cells.len() {
-                    return Err(RandStatOverflowError {});
-                }
-                cells[index] = init.value;
-                index += 1;
+enum Table<T> {
+    /// 100 cells, each representing a probability of .01 as an integer percentage.
+    Cells(Vec<T>),
+    /// A Vose's alias table built from arbitrary non-negative weights, sampled in O(1).
+    Alias {
+        values: Vec<T>,
+        prob: Vec<f64>,
+        alias: Vec<usize>,
+        /// Each value's normalized probability, kept around for `probability()` introspection.
+        weights: Vec<f64>,
+    },
+    /// A single yes/no event, sampled by comparing one uniform draw against `p`.
+    Bernoulli { p: f64, hit: T, miss: T },
+}
+
+fn build_cells<T: Clone + Default>(
+    init_vec: &[StatInit<T>],
+) -> Result<Table<T>, RandStatOverflowError> {
+    let mut cells = vec![T::default(); 100];
+    let mut index: usize = 0;
+    for init in init_vec {
+        for _ in 0..init.percentage {
+            if index >= cells.len() {
+                return Err(RandStatOverflowError {});
             }
+            cells[index] = init.value.clone();
+            index += 1;
+        }
+    }
+    Ok(Table::Cells(cells))
+}
+
+/// Builds a Vose's alias table from arbitrary non-negative weights.
+fn build_alias_table<T: Clone>(weights: &[(T, f64)]) -> Result<Table<T>, RandStatWeightError> {
+    let n = weights.len();
+    let total: f64 = weights.iter().map(|(_, w)| w).sum();
+    if n == 0 || total.is_nan() || total <= 0.0 || weights.iter().any(|(_, w)| *w < 0.0) {
+        return Err(RandStatWeightError {});
+    }
+
+    let values: Vec<T> = weights.iter().map(|(v, _)| v.clone()).collect();
+    let normalized: Vec<f64> = weights.iter().map(|(_, w)| w / total).collect();
+    let mut q: Vec<f64> = normalized.iter().map(|p| p * n as f64).collect();
+
+    let mut small: Vec<usize> = Vec::new();
+    let mut large: Vec<usize> = Vec::new();
+    for (i, &qi) in q.iter().enumerate() {
+        if qi < 1.0 {
+            small.push(i);
+        } else {
+            large.push(i);
         }
-        Ok(RandStat { cells })
     }
+
+    let mut prob = vec![0.0; n];
+    let mut alias = vec![0; n];
+
+    while !small.is_empty() && !large.is_empty() {
+        let s = small.pop().unwrap();
+        let l = large.pop().unwrap();
+        prob[s] = q[s];
+        alias[s] = l;
+        q[l] -= 1.0 - q[s];
+        if q[l] < 1.0 {
+            small.push(l);
+        } else {
+            large.push(l);
+        }
+    }
+
+    // Floating-point drift can leave a near-1 value in the wrong list; both remaining lists
+    // (not just one) may be non-empty, so drain them both as certain outcomes.
+    for i in large.into_iter().chain(small) {
+        prob[i] = 1.0;
+    }
+
+    Ok(Table::Alias {
+        values,
+        prob,
+        alias,
+        weights: normalized,
+    })
 }
 
-impl Default for RandStat {
+impl<T: Clone + Default> RandStat<T, ThreadRng> {
+    pub fn new(init_vec: &[StatInit<T>]) -> Result<Self, RandStatOverflowError> {
+        Self::from_rng(rand::thread_rng(), init_vec)
+    }
+}
+
+impl<T: Clone> RandStat<T, ThreadRng> {
+    /// Builds a `RandStat` that samples from arbitrary non-negative weights via Vose's alias
+    /// method, rather than the 100-cell integer-percentage table. This allows sub-percent
+    /// probabilities (e.g. a 0.5% packet-corruption rate) and more than 100 distinct outcomes.
+    pub fn weighted(weights: &[(T, f64)]) -> Result<Self, RandStatWeightError> {
+        Self::weighted_from_rng(rand::thread_rng(), weights)
+    }
+
+    /// Builds a `RandStat` for a single yes/no event, without building a cell or alias table.
+    /// Draws `hit` with probability `p` and `miss` otherwise.
+    pub fn bernoulli(p: f64, hit: T, miss: T) -> Result<Self, RandStatProbabilityError> {
+        Self::bernoulli_from_rng(rand::thread_rng(), p, hit, miss)
+    }
+}
+
+impl<T: Clone, R: Rng> RandStat<T, R> {
+    /// Builds a `RandStat` driven by a caller-supplied generator, sampling from arbitrary
+    /// non-negative weights via Vose's alias method. Like [`RandStat::from_rng`], this picks an
+    /// outcome via `gen_range`, which rejection-samples to stay unbiased; a low-entropy mock
+    /// generator can hang forever here for the same reason. Use a full-entropy generator (e.g.
+    /// `StdRng`) or, for a mock that's guaranteed to terminate, [`RandStat::bernoulli_from_rng`].
+    pub fn weighted_from_rng(rng: R, weights: &[(T, f64)]) -> Result<Self, RandStatWeightError> {
+        let table = build_alias_table(weights)?;
+        Ok(RandStat { table, rng })
+    }
+
+    /// Builds a `RandStat` for a single yes/no event, driven by a caller-supplied generator.
+    /// Draws `hit` with probability `p` and `miss` otherwise, via a single `gen::<f64>()` draw
+    /// per step with no rejection loop — unlike [`RandStat::from_rng`] and
+    /// [`RandStat::weighted_from_rng`], this is safe to drive with a low-entropy mock like rand's
+    /// `StepRng` and is guaranteed to terminate (see the `mock_rng` example).
+    pub fn bernoulli_from_rng(
+        rng: R,
+        p: f64,
+        hit: T,
+        miss: T,
+    ) -> Result<Self, RandStatProbabilityError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(RandStatProbabilityError {});
+        }
+        Ok(RandStat {
+            table: Table::Bernoulli { p, hit, miss },
+            rng,
+        })
+    }
+}
+
+impl<T: Clone + Default> RandStat<T, StdRng> {
+    /// Builds a `RandStat` driven by a `StdRng` seeded from `seed`, so the emitted stream is
+    /// identical across runs. Useful for regression tests that replay a simulated network trace.
+    pub fn seed_from_u64(
+        seed: u64,
+        init_vec: &[StatInit<T>],
+    ) -> Result<Self, RandStatOverflowError> {
+        Self::from_rng(StdRng::seed_from_u64(seed), init_vec)
+    }
+}
+
+impl<T: Clone + Default, R: Rng> RandStat<T, R> {
+    /// Builds a `RandStat` driven by a caller-supplied generator. The cell table is sampled via
+    /// `gen_range`, which rejection-samples to stay unbiased ([`RandStat::weighted_from_rng`]'s
+    /// alias table does the same over its outcome count). A full-entropy generator such as
+    /// [`RandStat::seed_from_u64`]'s `StdRng` gives a reproducible stream safely, but a
+    /// low-entropy mock like rand's `StepRng` can cycle through only rejected values and hang
+    /// forever. For a deterministic mock that's guaranteed to terminate, use
+    /// [`RandStat::bernoulli_from_rng`] instead (see the `mock_rng` example): it draws one
+    /// `f64` per step with no rejection loop.
+    pub fn from_rng(rng: R, init_vec: &[StatInit<T>]) -> Result<Self, RandStatOverflowError> {
+        let table = build_cells(init_vec)?;
+        Ok(RandStat { table, rng })
+    }
+}
+
+impl<T: Clone + Default> Default for RandStat<T, ThreadRng> {
     fn default() -> Self {
-        RandStat { cells: [0; 100] }
+        RandStat {
+            table: Table::Cells(vec![T::default(); 100]),
+            rng: rand::thread_rng(),
+        }
     }
 }
 
-/// Returns a stream of status bytes
-impl Iterator for RandStat {
-    type Item = u8;
+/// Returns a stream of status values
+impl<T: Clone, R: Rng> Iterator for RandStat<T, R> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut rng = rand::thread_rng();
-        let index: usize = rng.gen::<usize>() % self.cells.len();
-        Some(self.cells[index])
+        match &self.table {
+            Table::Cells(cells) => {
+                let index = self.rng.gen_range(0..cells.len());
+                Some(cells[index].clone())
+            }
+            Table::Alias {
+                values,
+                prob,
+                alias,
+                ..
+            } => {
+                let i = self.rng.gen_range(0..values.len());
+                let u: f64 = self.rng.gen();
+                let idx = if u < prob[i] { i } else { alias[i] };
+                Some(values[idx].clone())
+            }
+            Table::Bernoulli { p, hit, miss } => {
+                let u: f64 = self.rng.gen();
+                Some(if u < *p { hit.clone() } else { miss.clone() })
+            }
+        }
+    }
+}
+
+impl<T: Clone + PartialEq, R: Rng> RandStat<T, R> {
+    /// Reports the configured probability of drawing `value`, so tests can assert the
+    /// distribution was built as intended without sampling it.
+    pub fn probability(&self, value: &T) -> f64 {
+        match &self.table {
+            Table::Cells(cells) => {
+                let matches = cells.iter().filter(|c| *c == value).count();
+                matches as f64 / cells.len() as f64
+            }
+            Table::Alias {
+                values, weights, ..
+            } => values
+                .iter()
+                .zip(weights.iter())
+                .filter(|(v, _)| *v == value)
+                .map(|(_, w)| w)
+                .sum(),
+            Table::Bernoulli { p, hit, miss } => {
+                if value == hit {
+                    *p
+                } else if value == miss {
+                    1.0 - *p
+                } else {
+                    0.0
+                }
+            }
+        }
     }
 }
 
@@ -141,7 +396,7 @@ mod tests {
                 value: 0x02,
             },
         ];
-        let rs = RandStat::new(&init);
+        let rs: Result<RandStat, _> = RandStat::new(&init);
         assert!(rs.is_err());
     }
 
@@ -167,4 +422,121 @@ mod tests {
             assert!(test_vec.contains(&i));
         }
     }
+
+    #[test]
+    fn seeded_streams_are_reproducible() {
+        let init = vec![
+            StatInit {
+                percentage: 50,
+                value: 0x01,
+            },
+            StatInit {
+                percentage: 50,
+                value: 0x02,
+            },
+        ];
+        let a: Vec<u8> = RandStat::seed_from_u64(42, &init)
+            .unwrap()
+            .take(1000)
+            .collect();
+        let b: Vec<u8> = RandStat::seed_from_u64(42, &init)
+            .unwrap()
+            .take(1000)
+            .collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn mock_rng_yields_a_scripted_sequence() {
+        use rand::rngs::mock::StepRng;
+
+        // A step of `1 << 62` wraps exactly every 4th draw (no drift), so `bernoulli_from_rng`
+        // (a single `f64` draw per step, no range-rejection sampling) produces an exact, known,
+        // terminating sequence: hit, hit, miss, miss, repeating.
+        let rng = StepRng::new(0, 1u64 << 62);
+        let rs = RandStat::bernoulli_from_rng(rng, 0.5, 0x01, 0x02).unwrap();
+        let got: Vec<u8> = rs.take(8).collect();
+        assert_eq!(got, vec![0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x02, 0x02]);
+    }
+
+    #[test]
+    fn can_create_weighted() {
+        let rs: RandStat<u8> = RandStat::weighted(&[(0x01, 0.005), (0x02, 0.995)]).unwrap();
+        for i in rs.take(1000) {
+            assert!(i == 0x01 || i == 0x02);
+        }
+    }
+
+    #[test]
+    fn weighted_rejects_all_zero_weights() {
+        let rs: Result<RandStat<u8>, _> = RandStat::weighted(&[(0x01, 0.0), (0x02, 0.0)]);
+        assert!(rs.is_err());
+    }
+
+    #[test]
+    fn weighted_rejects_empty_weights() {
+        let rs: Result<RandStat<u8>, _> = RandStat::weighted(&[]);
+        assert!(rs.is_err());
+    }
+
+    #[test]
+    fn can_emit_non_u8_values() {
+        #[derive(Debug, Clone, Default, PartialEq)]
+        enum Status {
+            #[default]
+            Ok,
+            Timeout,
+        }
+
+        let init = vec![StatInit {
+            percentage: 100,
+            value: Status::Timeout,
+        }];
+        let rs: RandStat<Status> = RandStat::new(&init).unwrap();
+        for status in rs.take(1000) {
+            assert_eq!(status, Status::Timeout);
+        }
+    }
+
+    #[test]
+    fn can_create_bernoulli() {
+        let rs: RandStat<&str> = RandStat::bernoulli(0.3, "lost", "delivered").unwrap();
+        for msg in rs.take(1000) {
+            assert!(msg == "lost" || msg == "delivered");
+        }
+    }
+
+    #[test]
+    fn bernoulli_rejects_out_of_range_probability() {
+        let rs: Result<RandStat<&str>, _> = RandStat::bernoulli(1.5, "lost", "delivered");
+        assert!(rs.is_err());
+    }
+
+    #[test]
+    fn probability_reports_bernoulli_chance() {
+        let rs: RandStat<&str> = RandStat::bernoulli(0.3, "lost", "delivered").unwrap();
+        assert_eq!(rs.probability(&"lost"), 0.3);
+        assert_eq!(rs.probability(&"delivered"), 0.7);
+    }
+
+    #[test]
+    fn probability_reports_cell_and_alias_chance() {
+        let init = vec![
+            StatInit {
+                percentage: 25,
+                value: 0x01,
+            },
+            StatInit {
+                percentage: 75,
+                value: 0x02,
+            },
+        ];
+        let rs: RandStat<u8> = RandStat::new(&init).unwrap();
+        assert_eq!(rs.probability(&0x01), 0.25);
+        assert_eq!(rs.probability(&0x02), 0.75);
+
+        let weighted: RandStat<u8> = RandStat::weighted(&[(0x01, 1.0), (0x02, 3.0)]).unwrap();
+        assert_eq!(weighted.probability(&0x01), 0.25);
+        assert_eq!(weighted.probability(&0x02), 0.75);
+    }
 }